@@ -1,25 +1,56 @@
-#![no_main]
-#![no_std]
+#![cfg_attr(not(test), no_main)]
+#![cfg_attr(not(test), no_std)]
+
+mod split;
 
 use core::convert::Infallible;
 use embedded_hal::digital::v2::{InputPin, OutputPin};
 use generic_array::typenum::{U1, U2};
-use keyberon::action::k;
+use heapless::spsc::{Consumer, Producer, Queue};
+use keyberon::action::{k, l, m, Action, HoldTapAction, HoldTapConfig};
 use keyberon::debounce::Debouncer;
 use keyberon::impl_heterogenous_array;
 use keyberon::key_code::KeyCode::*;
-use keyberon::key_code::{KbHidReport, KeyCode};
-use keyberon::layout::Layout;
+use keyberon::key_code::KbHidReport;
+use keyberon::layout::{CustomEvent, Event, Layout};
 use keyberon::matrix::{Matrix, PressedKeys};
 use panic_semihosting as _;
-use rtfm::app;
+use rtic::app;
+use rtic::Mutex;
+use smart_leds::{brightness, SmartLedsWrite, RGB8};
+use split::Decoder;
 use stm32f1xx_hal::gpio::{gpioa::*, Input, Output, PullUp, PushPull};
 use stm32f1xx_hal::prelude::*;
+use stm32f1xx_hal::serial::{Config as SerialConfig, Rx, Serial, Tx};
+use stm32f1xx_hal::spi::{Spi, Spi1NoRemap};
 use stm32f1xx_hal::usb::{Peripheral, UsbBus, UsbBusType};
-use stm32f1xx_hal::{gpio, pac, timer};
+use stm32f1xx_hal::{gpio, pac};
+use systick_monotonic::{ExtU64, Systick};
 use usb_device::bus::UsbBusAllocator;
 use usb_device::class::UsbClass as _;
 use usb_device::prelude::{UsbDeviceBuilder, UsbVidPid};
+use ws2812_spi::Ws2812;
+
+/// Number of columns scanned by this half's own matrix. The other half's
+/// column indices, as received over UART, are offset by this so both
+/// halves' keys land in disjoint slots of the combined logical matrix.
+const LOCAL_COLS: u8 = 2;
+
+type SerialRx = Rx<pac::USART2>;
+type SerialTx = Tx<pac::USART2>;
+
+const N_LEDS: usize = 8;
+
+type BacklightSpi = Spi<
+    pac::SPI1,
+    Spi1NoRemap,
+    (
+        PA5<gpio::Alternate<PushPull>>,
+        PA6<Input<gpio::Floating>>,
+        PA7<gpio::Alternate<PushPull>>,
+    ),
+    u8,
+>;
 
 // Generic keyboard from
 // https://github.com/obdev/v-usb/blob/master/usbdrv/USB-IDs-for-free.txt
@@ -31,6 +62,7 @@ type UsbDevice = keyberon::Device<'static, UsbBusType>;
 
 pub struct Leds {
     caps_lock: gpio::gpiob::PB2<gpio::Output<gpio::PushPull>>,
+    backlight: Backlight,
 }
 impl keyberon::keyboard::Leds for Leds {
     fn caps_lock(&mut self, active: bool) {
@@ -39,6 +71,93 @@ impl keyberon::keyboard::Leds for Leds {
         } else {
             self.caps_lock.set_low().unwrap()
         }
+        self.backlight.set_caps(active);
+    }
+}
+
+/// Per-layer base colors, indexed by the active layout layer.
+const LAYER_COLORS: &[RGB8] = &[
+    RGB8::new(0, 0, 20),
+    RGB8::new(0, 20, 0),
+    RGB8::new(20, 0, 0),
+    RGB8::new(20, 20, 0),
+    RGB8::new(20, 0, 20),
+];
+
+/// Brightness levels cycled through by `CustomAction::BacklightCycle`.
+const BRIGHTNESS_LEVELS: &[u8] = &[10, 40, 120, 255];
+
+/// Drives a WS2812 chain over SPI, showing the active layer as a color.
+/// While caps lock is on (and the caps indicator is enabled) that color is
+/// shown at full brightness instead of the selected level; the strip does
+/// not flash and does not change color for caps lock.
+pub struct Backlight {
+    strip: Ws2812<BacklightSpi>,
+    layer: u8,
+    caps: bool,
+    brightness_idx: usize,
+    caps_indicator_enabled: bool,
+    dirty: bool,
+}
+
+impl Backlight {
+    pub fn new(spi: BacklightSpi) -> Self {
+        Backlight {
+            strip: Ws2812::new(spi),
+            layer: 0,
+            caps: false,
+            brightness_idx: 1,
+            caps_indicator_enabled: true,
+            dirty: true,
+        }
+    }
+
+    pub fn set_layer(&mut self, layer: u8) {
+        if self.layer != layer {
+            self.layer = layer;
+            self.dirty = true;
+        }
+    }
+
+    pub fn set_caps(&mut self, caps: bool) {
+        if self.caps != caps {
+            self.caps = caps;
+            self.dirty = true;
+        }
+    }
+
+    /// Steps to the next brightness level, wrapping back to the dimmest.
+    pub fn cycle_brightness(&mut self) {
+        self.brightness_idx = (self.brightness_idx + 1) % BRIGHTNESS_LEVELS.len();
+        self.dirty = true;
+    }
+
+    /// Toggles whether caps lock brightens the strip at all.
+    pub fn toggle_caps_indicator(&mut self) {
+        self.caps_indicator_enabled = !self.caps_indicator_enabled;
+        self.dirty = true;
+    }
+
+    /// Re-renders the strip from the current layer/caps state, but only if
+    /// something changed since the last call.
+    pub fn update(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        self.dirty = false;
+
+        let base = LAYER_COLORS
+            .get(self.layer as usize)
+            .copied()
+            .unwrap_or_else(|| RGB8::new(20, 20, 20));
+        let colors = [base; N_LEDS];
+
+        let level = if self.caps && self.caps_indicator_enabled {
+            255
+        } else {
+            BRIGHTNESS_LEVELS[self.brightness_idx]
+        };
+        let _ = self.strip.write(brightness(colors.iter().cloned(), level));
     }
 }
 
@@ -58,27 +177,121 @@ impl_heterogenous_array! {
     [0]
 }
 
+/// Board-control actions that never reach the host as keystrokes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CustomAction {
+    /// Step the backlight to its next brightness level.
+    BacklightCycle,
+    /// Toggle whether caps lock brightens the backlight.
+    CapsIndicatorToggle,
+    /// Reset into the STM32 system bootloader for DFU flashing.
+    EnterBootloader,
+}
+
+/// Tap `LShift`, hold for the arrow layer.
+const HT_ARROWS: Action<CustomAction> = Action::HoldTap(&HoldTapAction {
+    timeout: 200,
+    hold: l(1),
+    tap: k(LShift),
+    config: HoldTapConfig::Default,
+});
+
+/// Tap `LCtrl`, hold for the media layer.
+const HT_MEDIA: Action<CustomAction> = Action::HoldTap(&HoldTapAction {
+    timeout: 200,
+    hold: l(2),
+    tap: k(LCtrl),
+    config: HoldTapConfig::Default,
+});
+
+/// Tap volume-down, hold for the board-control layer.
+const HT_BOARD_CONTROL: Action<CustomAction> = Action::HoldTap(&HoldTapAction {
+    timeout: 200,
+    hold: l(3),
+    tap: k(MediaVolDown),
+    config: HoldTapConfig::Default,
+});
+
+/// Tap toggles the caps indicator, hold reaches the bootloader layer.
+const HT_BOOTLOADER: Action<CustomAction> = Action::HoldTap(&HoldTapAction {
+    timeout: 500,
+    hold: l(4),
+    tap: Action::Custom(CustomAction::CapsIndicatorToggle),
+    config: HoldTapConfig::Default,
+});
+
+// Columns 0-1 are this half's own two keys; columns 2-3 are the secondary
+// half's two keys, landing here at `LOCAL_COLS` offset (see
+// `offset_remote_event`). Every layer must stay this wide or the secondary
+// half's events fall outside the layout and silently no-op.
 #[rustfmt::skip]
-pub static LAYERS: keyberon::layout::Layers = &[
+pub static LAYERS: keyberon::layout::Layers<CustomAction> = &[
+    // 0: base layer - hold-taps into the arrow/media layers, secondary half
+    // contributes Space/Backspace
     &[
-        &[k(LShift), k(LCtrl)],
+        &[HT_ARROWS, HT_MEDIA, k(Space), k(BSpace)],
+    ],
+    // 1: arrows, plus a Ctrl+Alt+Delete combo on the second key
+    &[
+        &[k(Left), m(&[k(LCtrl), k(LAlt), k(Delete)]), k(Up), k(Down)],
+    ],
+    // 2: media keys - holding volume-down reaches the board-control layer
+    &[
+        &[k(MediaVolUp), HT_BOARD_CONTROL, k(MediaPlayPause), k(MediaVolDown)],
+    ],
+    // 3: board control - never sends a keystroke to the host
+    &[
+        &[Action::Custom(CustomAction::BacklightCycle), HT_BOOTLOADER, Action::Trans, Action::Trans],
+    ],
+    // 4: held long enough from layer 3, jumps straight to DFU
+    &[
+        &[Action::Trans, Action::Custom(CustomAction::EnterBootloader), Action::Trans, Action::Trans],
     ],
 ];
 
-#[app(device = stm32f1xx_hal::pac, peripherals = true)]
-const APP: () = {
-    struct Resources {
+#[app(device = stm32f1xx_hal::pac, peripherals = true, dispatchers = [SPI1, SPI2])]
+mod app {
+    use super::*;
+
+    #[monotonic(binds = SysTick, default = true)]
+    type MyMono = Systick<1000>;
+
+    #[shared]
+    struct Shared {
+        #[cfg(not(feature = "secondary"))]
         usb_dev: UsbDevice,
+        #[cfg(not(feature = "secondary"))]
         usb_class: UsbClass,
+    }
+
+    #[local]
+    struct Local {
         matrix: Matrix<Cols, Rows>,
         debouncer: Debouncer<PressedKeys<U1, U2>>,
-        layout: Layout,
-        timer: timer::CountDownTimer<pac::TIM3>,
+        #[cfg(not(feature = "secondary"))]
+        layout: Layout<CustomAction>,
+        #[cfg(not(feature = "secondary"))]
+        serial_rx: SerialRx,
+        #[cfg(not(feature = "secondary"))]
+        remote_decoder: Decoder,
+        #[cfg(not(feature = "secondary"))]
+        remote_producer: Producer<'static, Event, 32>,
+        #[cfg(not(feature = "secondary"))]
+        remote_events: Consumer<'static, Event, 32>,
+        #[cfg(feature = "secondary")]
+        serial_tx: SerialTx,
     }
 
     #[init]
-    fn init(c: init::Context) -> init::LateResources {
+    fn init(c: init::Context) -> (Shared, Local, init::Monotonics) {
+        #[cfg(not(feature = "secondary"))]
         static mut USB_BUS: Option<UsbBusAllocator<UsbBusType>> = None;
+        // Sized with headroom well beyond the secondary half's 2 keys so a
+        // burst of edges can queue up across several ticks without
+        // overflowing; serial_rx debug_asserts if it ever does (builds
+        // without debug assertions drop the event).
+        #[cfg(not(feature = "secondary"))]
+        static mut REMOTE_EVENTS: Queue<Event, 32> = Queue::new();
 
         let mut flash = c.device.FLASH.constrain();
         let mut rcc = c.device.RCC.constrain();
@@ -90,34 +303,64 @@ const APP: () = {
             .pclk1(24.mhz())
             .freeze(&mut flash.acr);
 
+        let mut afio = c.device.AFIO.constrain(&mut rcc.apb2);
         let mut gpioa = c.device.GPIOA.split(&mut rcc.apb2);
+        #[cfg(not(feature = "secondary"))]
         let mut gpiob = c.device.GPIOB.split(&mut rcc.apb2);
 
+        #[cfg(not(feature = "secondary"))]
         let led = gpiob.pb2.into_push_pull_output(&mut gpiob.crl);
-        let leds = Leds { caps_lock: led };
 
+        #[cfg(not(feature = "secondary"))]
+        let backlight_spi = Spi::spi1(
+            c.device.SPI1,
+            (
+                gpioa.pa5.into_alternate_push_pull(&mut gpioa.crl),
+                gpioa.pa6.into_floating_input(&mut gpioa.crl),
+                gpioa.pa7.into_alternate_push_pull(&mut gpioa.crl),
+            ),
+            &mut afio.mapr,
+            ws2812_spi::MODE,
+            3.mhz(),
+            clocks,
+            &mut rcc.apb2,
+        );
+        #[cfg(not(feature = "secondary"))]
+        let backlight = Backlight::new(backlight_spi);
+        #[cfg(not(feature = "secondary"))]
+        let leds = Leds {
+            caps_lock: led,
+            backlight,
+        };
+
+        #[cfg(not(feature = "secondary"))]
         let usb_dm = gpioa.pa11;
+        #[cfg(not(feature = "secondary"))]
         let usb_dp = gpioa.pa12.into_floating_input(&mut gpioa.crh);
 
+        #[cfg(not(feature = "secondary"))]
         let usb = Peripheral {
             usb: c.device.USB,
             pin_dm: usb_dm,
             pin_dp: usb_dp,
         };
 
-        *USB_BUS = Some(UsbBus::new(usb));
-        let usb_bus = USB_BUS.as_ref().unwrap();
-
-        let usb_class = keyberon::new_class(usb_bus, leds);
-        let usb_dev = UsbDeviceBuilder::new(usb_bus, UsbVidPid(VID, PID))
-            .manufacturer("Null")
-            .product("LT-3")
-            .serial_number(env!("CARGO_PKG_VERSION"))
-            .build();
+        #[cfg(not(feature = "secondary"))]
+        let usb_class;
+        #[cfg(not(feature = "secondary"))]
+        let usb_dev;
+        #[cfg(not(feature = "secondary"))]
+        {
+            *USB_BUS = Some(UsbBus::new(usb));
+            let usb_bus = USB_BUS.as_ref().unwrap();
 
-        let mut timer =
-            timer::Timer::tim3(c.device.TIM3, &clocks, &mut rcc.apb1).start_count_down(1.khz());
-        timer.listen(timer::Event::Update);
+            usb_class = keyberon::new_class(usb_bus, leds);
+            usb_dev = UsbDeviceBuilder::new(usb_bus, UsbVidPid(VID, PID))
+                .manufacturer("Null")
+                .product("LT-3")
+                .serial_number(env!("CARGO_PKG_VERSION"))
+                .build();
+        }
 
         let matrix = Matrix::new(
             Cols(
@@ -127,51 +370,232 @@ const APP: () = {
             Rows(gpioa.pa8.into_push_pull_output(&mut gpioa.crh)),
         );
 
-        init::LateResources {
-            usb_dev,
-            usb_class,
-            timer,
-            debouncer: Debouncer::new(PressedKeys::default(), PressedKeys::default(), 5),
-            matrix: matrix.unwrap(),
-            layout: Layout::new(LAYERS),
-        }
+        let serial = Serial::usart2(
+            c.device.USART2,
+            (
+                gpioa.pa2.into_alternate_push_pull(&mut gpioa.crl),
+                gpioa.pa3,
+            ),
+            &mut afio.mapr,
+            SerialConfig::default().baudrate(115_200.bps()),
+            clocks,
+            &mut rcc.apb1,
+        );
+
+        #[cfg(feature = "secondary")]
+        let (serial_tx, _serial_rx) = serial.split();
+
+        #[cfg(not(feature = "secondary"))]
+        let (_serial_tx, mut serial_rx) = serial.split();
+        #[cfg(not(feature = "secondary"))]
+        serial_rx.listen();
+        #[cfg(not(feature = "secondary"))]
+        let (remote_producer, remote_events) = REMOTE_EVENTS.split();
+
+        let mono = Systick::new(c.core.SYST, clocks.sysclk().0);
+        tick::spawn_after(1.millis()).unwrap();
+
+        (
+            Shared {
+                #[cfg(not(feature = "secondary"))]
+                usb_dev,
+                #[cfg(not(feature = "secondary"))]
+                usb_class,
+            },
+            Local {
+                matrix: matrix.unwrap(),
+                debouncer: Debouncer::new(PressedKeys::default(), PressedKeys::default(), 5),
+                #[cfg(not(feature = "secondary"))]
+                layout: Layout::new(LAYERS),
+                #[cfg(not(feature = "secondary"))]
+                serial_rx,
+                #[cfg(not(feature = "secondary"))]
+                remote_decoder: Decoder::new(),
+                #[cfg(not(feature = "secondary"))]
+                remote_producer,
+                #[cfg(not(feature = "secondary"))]
+                remote_events,
+                #[cfg(feature = "secondary")]
+                serial_tx,
+            },
+            init::Monotonics(mono),
+        )
+    }
+
+    #[cfg(not(feature = "secondary"))]
+    #[task(binds = USB_HP_CAN_TX, priority = 2)]
+    fn usb_tx(_c: usb_tx::Context) {
+        usb_poll::spawn().ok();
     }
 
-    #[task(binds = USB_HP_CAN_TX, priority = 2, resources = [usb_dev, usb_class])]
-    fn usb_tx(mut c: usb_tx::Context) {
-        usb_poll(&mut c.resources.usb_dev, &mut c.resources.usb_class);
+    #[cfg(not(feature = "secondary"))]
+    #[task(binds = USB_LP_CAN_RX0, priority = 2)]
+    fn usb_rx(_c: usb_rx::Context) {
+        usb_poll::spawn().ok();
     }
 
-    #[task(binds = USB_LP_CAN_RX0, priority = 2, resources = [usb_dev, usb_class])]
-    fn usb_rx(mut c: usb_rx::Context) {
-        usb_poll(&mut c.resources.usb_dev, &mut c.resources.usb_class);
+    #[cfg(not(feature = "secondary"))]
+    #[task(priority = 2, shared = [usb_dev, usb_class])]
+    fn usb_poll(c: usb_poll::Context) {
+        (c.shared.usb_dev, c.shared.usb_class).lock(|usb_dev, usb_class| {
+            if usb_dev.poll(&mut [usb_class]) {
+                usb_class.poll();
+            }
+        });
+    }
+
+    #[cfg(not(feature = "secondary"))]
+    #[task(binds = USART2, priority = 2, local = [serial_rx, remote_decoder, remote_producer])]
+    fn serial_rx(c: serial_rx::Context) {
+        while let Ok(byte) = c.local.serial_rx.read() {
+            if let Some(event) = c.local.remote_decoder.feed(byte) {
+                let enqueued = c
+                    .local
+                    .remote_producer
+                    .enqueue(offset_remote_event(event))
+                    .is_ok();
+                debug_assert!(enqueued, "remote event queue overflowed, dropped a key edge");
+            }
+        }
     }
 
-    #[task(binds = TIM3, priority = 1, resources = [usb_class, matrix, debouncer, layout, timer])]
+    #[cfg(not(feature = "secondary"))]
+    #[task(priority = 1, local = [matrix, debouncer, layout, remote_events], shared = [usb_class])]
     fn tick(mut c: tick::Context) {
-        c.resources.timer.clear_update_interrupt_flag();
+        for event in c.local.debouncer.events(c.local.matrix.get().unwrap()) {
+            c.local.layout.event(event);
+        }
+        while let Some(event) = c.local.remote_events.dequeue() {
+            c.local.layout.event(event);
+        }
+        let custom_event = c.local.layout.tick();
+        let report: KbHidReport = c.local.layout.keycodes().collect();
 
-        for event in c
-            .resources
-            .debouncer
-            .events(c.resources.matrix.get().unwrap())
-        {
-            send_report(c.resources.layout.event(event), &mut c.resources.usb_class);
+        send_report(report, &mut c.shared.usb_class);
+        handle_custom_event(custom_event, &mut c.shared.usb_class);
+
+        update_backlight::spawn(c.local.layout.current_layer() as u8).ok();
+        tick::spawn_after(1.millis()).unwrap();
+    }
+
+    #[cfg(feature = "secondary")]
+    #[task(priority = 1, local = [matrix, debouncer, serial_tx])]
+    fn tick(c: tick::Context) {
+        for event in c.local.debouncer.events(c.local.matrix.get().unwrap()) {
+            for byte in split::encode(event).iter() {
+                while c.local.serial_tx.write(*byte).is_err() {}
+            }
         }
-        send_report(c.resources.layout.tick(), &mut c.resources.usb_class);
+        tick::spawn_after(1.millis()).unwrap();
     }
-};
 
-fn send_report(iter: impl Iterator<Item = KeyCode>, usb_class: &mut resources::usb_class<'_>) {
-    use rtfm::Mutex;
-    let report: KbHidReport = iter.collect();
-    if usb_class.lock(|k| k.device_mut().set_keyboard_report(report.clone())) {
-        while let Ok(0) = usb_class.lock(|k| k.write(report.as_bytes())) {}
+    #[cfg(not(feature = "secondary"))]
+    #[task(priority = 1, shared = [usb_class])]
+    fn update_backlight(mut c: update_backlight::Context, layer: u8) {
+        c.shared.usb_class.lock(|usb_class| {
+            let backlight = &mut usb_class.device_mut().leds_mut().backlight;
+            backlight.set_layer(layer);
+            backlight.update();
+        });
     }
 }
 
-fn usb_poll(usb_dev: &mut UsbDevice, keyboard: &mut UsbClass) {
-    if usb_dev.poll(&mut [keyboard]) {
-        keyboard.poll();
+/// Shifts a remote-half event's column into the combined matrix, past the
+/// columns this half scans locally.
+#[cfg(not(feature = "secondary"))]
+fn offset_remote_event(event: Event) -> Event {
+    match event {
+        Event::Press(row, col) => Event::Press(row, col + LOCAL_COLS),
+        Event::Release(row, col) => Event::Release(row, col + LOCAL_COLS),
     }
 }
+
+// Locks per write attempt rather than once for the whole retry loop:
+// usb_class is shared with the priority-2 usb_poll task, which is the only
+// thing that ever drains the endpoint and lets write() succeed. Holding the
+// lock across the whole spin would shut usb_poll out for as long as the
+// host leaves the report unread, deadlocking both tasks.
+#[cfg(not(feature = "secondary"))]
+fn send_report(report: KbHidReport, usb_class: &mut impl Mutex<T = UsbClass>) {
+    if usb_class.lock(|usb_class| usb_class.device_mut().set_keyboard_report(report.clone())) {
+        while let Ok(0) = usb_class.lock(|usb_class| usb_class.write(report.as_bytes())) {}
+    }
+}
+
+/// Runs the firmware-side handler for a board-control action, if any was
+/// triggered this tick. These never turn into keystrokes sent to the host.
+#[cfg(not(feature = "secondary"))]
+fn handle_custom_event(event: CustomEvent<CustomAction>, usb_class: &mut impl Mutex<T = UsbClass>) {
+    let action = match event {
+        CustomEvent::Press(action) => action,
+        CustomEvent::NoEvent | CustomEvent::Release(_) => return,
+    };
+    match action {
+        CustomAction::BacklightCycle => {
+            usb_class.lock(|usb_class| usb_class.device_mut().leds_mut().backlight.cycle_brightness());
+        }
+        CustomAction::CapsIndicatorToggle => {
+            usb_class.lock(|usb_class| {
+                usb_class
+                    .device_mut()
+                    .leds_mut()
+                    .backlight
+                    .toggle_caps_indicator();
+            });
+        }
+        CustomAction::EnterBootloader => enter_bootloader(),
+    }
+}
+
+/// Resets into the STM32F1 system memory bootloader so the board can be
+/// re-flashed over USB DFU without a physical BOOT0 jumper.
+#[cfg(not(feature = "secondary"))]
+fn enter_bootloader() -> ! {
+    const SYSTEM_MEMORY_BASE: u32 = 0x1FFF_F000;
+
+    cortex_m::interrupt::disable();
+    unsafe {
+        let sp = *(SYSTEM_MEMORY_BASE as *const u32);
+        let reset_vector = *((SYSTEM_MEMORY_BASE + 4) as *const u32);
+        cortex_m::register::msp::write(sp);
+        let bootloader: extern "C" fn() -> ! = core::mem::transmute(reset_vector);
+        bootloader();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Regression coverage for the LAYERS-width bug fixed earlier: columns
+    // 2-3 are where the secondary half's offset events land, so they must
+    // resolve to real actions instead of silently no-op'ing from falling
+    // outside LAYERS' bounds.
+
+    #[test]
+    fn secondary_half_col_2_resolves_to_space() {
+        let mut layout = Layout::new(LAYERS);
+
+        layout.event(Event::Press(0, 2));
+        layout.tick();
+        assert!(layout.keycodes().any(|kc| kc == Space));
+
+        layout.event(Event::Release(0, 2));
+        layout.tick();
+        assert!(!layout.keycodes().any(|kc| kc == Space));
+    }
+
+    #[test]
+    fn secondary_half_col_3_resolves_to_backspace() {
+        let mut layout = Layout::new(LAYERS);
+
+        layout.event(Event::Press(0, 3));
+        layout.tick();
+        assert!(layout.keycodes().any(|kc| kc == BSpace));
+
+        layout.event(Event::Release(0, 3));
+        layout.tick();
+        assert!(!layout.keycodes().any(|kc| kc == BSpace));
+    }
+}
+
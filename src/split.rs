@@ -0,0 +1,113 @@
+//! Wire protocol for bridging `keyberon::layout::Event`s between the two
+//! halves of a split board over a plain UART link.
+//!
+//! Each event is framed as three bytes: a sync byte, then a flags/row byte,
+//! then a column byte. A `SYNC` byte seen anywhere - even mid-frame - is
+//! always treated as the start of a new frame, so a single dropped byte
+//! costs only the frame it belonged to and the frames after it decode
+//! normally. The lost frame's event is not recovered: if it was a release,
+//! that key stays pressed until its next event arrives.
+
+use keyberon::layout::Event;
+
+/// Marks the start of a frame. It can't be produced by the flags/row or
+/// column bytes of a frame whose row is below 0x7f and whose col is below
+/// 0x80; `encode` does not check this (a press at row 0x7f, or a col of
+/// 0xFF, yields a byte equal to `SYNC`).
+const SYNC: u8 = 0xFF;
+const PRESS_FLAG: u8 = 0x80;
+
+/// Encodes an `Event` as the 3 bytes sent over the wire.
+pub fn encode(event: Event) -> [u8; 3] {
+    let (is_press, row, col) = match event {
+        Event::Press(row, col) => (true, row, col),
+        Event::Release(row, col) => (false, row, col),
+    };
+    let flags_row = row & 0x7f | if is_press { PRESS_FLAG } else { 0 };
+    [SYNC, flags_row, col]
+}
+
+#[derive(Clone, Copy)]
+enum State {
+    WaitSync,
+    WaitFlagsRow,
+    WaitCol { is_press: bool, row: u8 },
+}
+
+/// Byte-at-a-time decoder driven from the UART RX interrupt.
+pub struct Decoder {
+    state: State,
+}
+
+impl Decoder {
+    pub const fn new() -> Self {
+        Decoder {
+            state: State::WaitSync,
+        }
+    }
+
+    /// Feeds one received byte in. Returns `Some(event)` once a full frame
+    /// has been decoded; `col` is the raw column index as sent by the
+    /// remote half, not yet offset into the combined matrix.
+    pub fn feed(&mut self, byte: u8) -> Option<Event> {
+        // A SYNC byte always restarts framing, even mid-frame: a dropped
+        // byte must only cost the current frame, not desync every frame
+        // after it.
+        if byte == SYNC {
+            self.state = State::WaitFlagsRow;
+            return None;
+        }
+        match self.state {
+            State::WaitSync => None,
+            State::WaitFlagsRow => {
+                self.state = State::WaitCol {
+                    is_press: byte & PRESS_FLAG != 0,
+                    row: byte & 0x7f,
+                };
+                None
+            }
+            State::WaitCol { is_press, row } => {
+                self.state = State::WaitSync;
+                let col = byte;
+                Some(if is_press {
+                    Event::Press(row, col)
+                } else {
+                    Event::Release(row, col)
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_press_and_release() {
+        let mut decoder = Decoder::new();
+        for &event in &[Event::Press(1, 2), Event::Release(1, 2)] {
+            let bytes = encode(event);
+            assert_eq!(decoder.feed(bytes[0]), None);
+            assert_eq!(decoder.feed(bytes[1]), None);
+            assert_eq!(decoder.feed(bytes[2]), Some(event));
+        }
+    }
+
+    #[test]
+    fn dropped_byte_mid_frame_only_costs_that_frame() {
+        let mut decoder = Decoder::new();
+        let press = encode(Event::Press(0, 1));
+        let release = encode(Event::Release(0, 1));
+
+        // Feed the press frame but drop its column byte (e.g. UART noise),
+        // then feed a full release frame right after. The release's SYNC
+        // byte must resync the decoder instead of being consumed as the
+        // dropped column.
+        assert_eq!(decoder.feed(press[0]), None);
+        assert_eq!(decoder.feed(press[1]), None);
+        assert_eq!(decoder.feed(release[0]), None);
+        assert_eq!(decoder.feed(release[1]), None);
+        assert_eq!(decoder.feed(release[2]), Some(Event::Release(0, 1)));
+    }
+}